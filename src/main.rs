@@ -0,0 +1,14 @@
+mod translator_ast;
+mod translator_backend;
+mod translator_backend_vm;
+mod translator_backend_x86;
+mod translator_codegen;
+mod translator_control;
+mod translator_errors;
+mod translator_lexer;
+mod translator_repl;
+mod translator_span;
+
+fn main() {
+    translator_repl::run();
+}
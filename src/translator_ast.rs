@@ -0,0 +1,44 @@
+use crate::translator_lexer::Symbol;
+
+/// A parsed statement. Built by `Translator`, walked by a `Visitor` (e.g.
+/// `CodeGen`) to turn it into target code.
+pub enum Stmt {
+    If(Expr, Vec<Stmt>, Option<Vec<Stmt>>),
+    While(Expr, Vec<Stmt>),
+    Loop(Vec<Stmt>),
+    Repeat(Vec<Stmt>, Expr),
+    For(Symbol, Expr, Expr, Vec<Stmt>),
+    Do(Expr, Vec<Stmt>),
+    Other(Symbol),
+}
+
+/// A parsed expression
+pub enum Expr {
+    Num(i64),
+    Var(Symbol),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Compare(RelOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Copy)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Clone, Copy)]
+pub enum RelOp {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+}
+
+/// Walks a parsed `Stmt`/`Expr` tree. Implemented once today by `CodeGen`,
+/// but keeps the tree itself free of any knowledge of the target machine.
+pub trait Visitor {
+    fn visit_stmt(&mut self, stmt: &Stmt);
+    fn visit_expr(&mut self, expr: &Expr);
+}
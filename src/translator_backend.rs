@@ -0,0 +1,74 @@
+use crate::translator_ast::{BinOp, RelOp};
+
+/// Everything `CodeGen` needs from a target machine. Implementing this for
+/// a new target lets the same parsed program be retargeted without touching
+/// the parser or the AST walk.
+pub trait Backend {
+    /// Load a constant into the current value
+    fn load_const(&mut self, n: i64);
+
+    /// Load a variable's value into the current value
+    fn load_var(&mut self, name: &str);
+
+    /// Store the current value into a variable. No statement in the
+    /// grammar assigns to a plain variable yet (only `for`-loop bounds,
+    /// via `store_slot`), so every implementation carries this without a
+    /// caller until assignment statements are parsed
+    #[allow(dead_code)]
+    fn store_var(&mut self, name: &str);
+
+    /// Load a `for`-loop bound from its storage slot. Distinct from
+    /// `load_var`/`store_var` because the underlying storage model for loop
+    /// bounds isn't pinned down yet (see the `<somehow ...>` placeholders)
+    fn load_slot(&mut self, name: &str);
+
+    /// Store a `for`-loop bound into its storage slot
+    fn store_slot(&mut self, name: &str);
+
+    /// Set the current value aside so a second value can be computed and
+    /// combined with it via `binop`/`compare`
+    fn push(&mut self);
+
+    /// Combine the pushed value and the current value with a binary operator,
+    /// leaving the result as the current value
+    fn binop(&mut self, op: BinOp);
+
+    /// Compare the pushed value against the current value, leaving a 0/1
+    /// result as the current value
+    fn compare(&mut self, op: RelOp);
+
+    /// Preserve a scratch register/slot across a loop that needs one
+    fn save_scratch(&mut self);
+
+    /// Restore the scratch register/slot saved by `save_scratch`
+    fn restore_scratch(&mut self);
+
+    /// Jump to `name` if the last `binop` overflowed
+    fn jump_if_overflow(&mut self, name: &str);
+
+    /// Decrement `var` and jump back to `name` while it is non-zero
+    fn dec_and_jump_if_nonzero(&mut self, var: &str, name: &str);
+
+    /// Define a label
+    fn label(&mut self, name: &str);
+
+    /// Unconditional jump
+    fn jump(&mut self, name: &str);
+
+    /// Jump to `name` if the current value is zero
+    fn jump_if_zero(&mut self, name: &str);
+
+    /// Enter a loop that repeats for as many iterations as the current value
+    fn enter_loop(&mut self, name: &str);
+
+    /// Jump back to the top of a counted loop entered with `enter_loop`
+    fn loop_back(&mut self, name: &str);
+
+    /// Emit whatever the target needs to mark the end of the program
+    fn finish(&mut self);
+
+    /// Emit an `<other>` statement verbatim. `<other>` was never given a
+    /// real grammar of its own (see `Translator::other`), so this just
+    /// passes the bare name through as `block` always has
+    fn other(&mut self, name: &str);
+}
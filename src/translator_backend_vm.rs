@@ -0,0 +1,116 @@
+use std::io::Write;
+
+use crate::translator_ast::{BinOp, RelOp};
+use crate::translator_backend::Backend;
+
+/// A simple stack-machine target: one mnemonic per line, operating on an
+/// implicit value stack instead of named registers. Exists to prove the
+/// same parsed program can be retargeted through the `Backend` trait.
+pub struct VmBackend {
+    out: Box<dyn Write>,
+}
+
+impl VmBackend {
+    /// Not wired into the REPL yet (it only ever drives an `X86Backend`);
+    /// exercised directly by tests to prove `CodeGen` is backend-agnostic
+    #[allow(dead_code)]
+    pub fn new(out: Box<dyn Write>) -> VmBackend {
+        VmBackend { out }
+    }
+
+    fn emit(&mut self, s: &str) {
+        let _ = self.out.write_all(format!("{}\n", s).as_bytes());
+    }
+}
+
+impl Backend for VmBackend {
+    fn load_const(&mut self, n: i64) {
+        self.emit(&format!("PUSH {}", n));
+    }
+
+    fn load_var(&mut self, name: &str) {
+        self.emit(&format!("LOAD {}", name));
+    }
+
+    fn store_var(&mut self, name: &str) {
+        self.emit(&format!("STORE {}", name));
+    }
+
+    fn load_slot(&mut self, name: &str) {
+        self.emit(&format!("<somehow load {}>", name));
+    }
+
+    fn store_slot(&mut self, name: &str) {
+        self.emit(&format!("<somehow store to {}>", name));
+    }
+
+    fn push(&mut self) {
+        // the vm stack already holds both operands; nothing to do
+    }
+
+    fn binop(&mut self, op: BinOp) {
+        let mnemonic = match op {
+            BinOp::Add => "ADD",
+            BinOp::Sub => "SUB",
+            BinOp::Mul => "MUL",
+            BinOp::Div => "DIV",
+        };
+        self.emit(mnemonic);
+    }
+
+    fn compare(&mut self, op: RelOp) {
+        let mnemonic = match op {
+            RelOp::Eq => "CMPEQ",
+            RelOp::Ne => "CMPNE",
+            RelOp::Lt => "CMPLT",
+            RelOp::Gt => "CMPGT",
+        };
+        self.emit(mnemonic);
+    }
+
+    fn save_scratch(&mut self) {
+        // no registers to preserve on a pure stack machine
+    }
+
+    fn restore_scratch(&mut self) {
+        // no registers to preserve on a pure stack machine
+    }
+
+    fn jump_if_overflow(&mut self, name: &str) {
+        self.emit(&format!("JO {}", name));
+    }
+
+    fn dec_and_jump_if_nonzero(&mut self, var: &str, name: &str) {
+        self.emit(&format!("<somehow DEC {}>", var));
+        self.emit(&format!("JNZ {}", name));
+    }
+
+    fn label(&mut self, name: &str) {
+        self.emit(&format!("LABEL {}", name));
+    }
+
+    fn jump(&mut self, name: &str) {
+        self.emit(&format!("JMP {}", name));
+    }
+
+    fn jump_if_zero(&mut self, name: &str) {
+        self.emit(&format!("JZ {}", name));
+    }
+
+    fn enter_loop(&mut self, name: &str) {
+        self.emit("SETCOUNT");
+        self.emit(&format!("LABEL {}", name));
+    }
+
+    fn loop_back(&mut self, name: &str) {
+        self.emit(&format!("LOOPBACK {}", name));
+    }
+
+    fn finish(&mut self) {
+        self.emit("HALT");
+    }
+
+    fn other(&mut self, name: &str) {
+        self.emit(name);
+    }
+}
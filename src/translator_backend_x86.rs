@@ -0,0 +1,115 @@
+use std::io::Write;
+
+use crate::translator_ast::{BinOp, RelOp};
+use crate::translator_backend::Backend;
+
+/// Emits the same x86 mnemonics the translator used to `print!` directly,
+/// but through an arbitrary `Write`r instead of stdout
+pub struct X86Backend {
+    out: Box<dyn Write>,
+}
+
+impl X86Backend {
+    pub fn new(out: Box<dyn Write>) -> X86Backend {
+        X86Backend { out }
+    }
+
+    fn emit(&mut self, s: &str) {
+        let _ = self.out.write_all(format!("\t{}\n", s).as_bytes());
+    }
+}
+
+impl Backend for X86Backend {
+    fn load_const(&mut self, n: i64) {
+        self.emit(&format!("MOV EAX, {}", n));
+    }
+
+    fn load_var(&mut self, name: &str) {
+        self.emit(&format!("MOV EAX, {}", name));
+    }
+
+    fn store_var(&mut self, name: &str) {
+        self.emit(&format!("MOV {}, EAX", name));
+    }
+
+    fn load_slot(&mut self, name: &str) {
+        self.emit(&format!("<somehow load {}>", name));
+    }
+
+    fn store_slot(&mut self, name: &str) {
+        self.emit(&format!("<somehow store EAX to {}>", name));
+    }
+
+    fn push(&mut self) {
+        self.emit("PUSH EAX");
+    }
+
+    fn binop(&mut self, op: BinOp) {
+        self.emit("POP EBX");
+        match op {
+            BinOp::Add => self.emit("ADD EAX, EBX"),
+            BinOp::Sub => { self.emit("SUB EAX, EBX"); self.emit("NEG EAX"); }
+            BinOp::Mul => self.emit("IMUL EBX"),
+            BinOp::Div => { self.emit("XCHG EAX, EBX"); self.emit("CDQ"); self.emit("IDIV EBX"); }
+        }
+    }
+
+    fn compare(&mut self, op: RelOp) {
+        let setcc = match op {
+            RelOp::Eq => "SETE",
+            RelOp::Ne => "SETNE",
+            RelOp::Lt => "SETL",
+            RelOp::Gt => "SETG",
+        };
+        self.emit("POP EBX");
+        self.emit("CMP EBX, EAX");
+        self.emit(&format!("{} AL", setcc));
+        self.emit("MOVZX EAX, AL");
+    }
+
+    fn save_scratch(&mut self) {
+        self.emit("PUSH EBX");
+    }
+
+    fn restore_scratch(&mut self) {
+        self.emit("POP EBX");
+    }
+
+    fn jump_if_overflow(&mut self, name: &str) {
+        self.emit(&format!("JO {}", name));
+    }
+
+    fn dec_and_jump_if_nonzero(&mut self, var: &str, name: &str) {
+        self.emit(&format!("<somehow SUB {}, 1>", var));
+        self.emit(&format!("JNZ {}", name));
+    }
+
+    fn label(&mut self, name: &str) {
+        self.emit(&format!("{}:", name));
+    }
+
+    fn jump(&mut self, name: &str) {
+        self.emit(&format!("JMP {}", name));
+    }
+
+    fn jump_if_zero(&mut self, name: &str) {
+        self.emit(&format!("JZ {}", name));
+    }
+
+    fn enter_loop(&mut self, name: &str) {
+        self.emit("MOV ECX, EAX");
+        self.emit(&format!("{}:", name));
+    }
+
+    fn loop_back(&mut self, name: &str) {
+        self.emit(&format!("LOOP {}", name));
+    }
+
+    fn finish(&mut self) {
+        self.emit("END");
+    }
+
+    fn other(&mut self, name: &str) {
+        self.emit(name);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::translator_ast::{BinOp, Expr, Stmt, Visitor};
+use crate::translator_backend::Backend;
+use crate::translator_lexer::SymbolTable;
+
+/// Walks the parsed AST and drives a `Backend` to emit target code, owning
+/// the label counter that used to live on `Translator` itself
+pub struct CodeGen<'a> {
+    symbols: &'a SymbolTable,
+    backend: Box<dyn Backend + 'a>,
+    labels: usize,
+}
+
+impl<'a> CodeGen<'a> {
+    pub fn new(symbols: &'a SymbolTable, backend: Box<dyn Backend + 'a>) -> CodeGen<'a> {
+        CodeGen { symbols, backend, labels: 0 }
+    }
+
+    /// <program> ::= <block> END
+    pub fn generate(&mut self, program: &[Stmt]) {
+        for stmt in program.iter() {
+            self.visit_stmt(stmt);
+        }
+        self.backend.finish();
+    }
+
+    /// Generate a unique label
+    fn new_label(&mut self) -> String {
+        let res = format!("L{}", self.labels);
+        self.labels += 1;
+        res
+    }
+}
+
+impl<'a> Visitor for CodeGen<'a> {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match *stmt {
+            Stmt::If(ref cond, ref then, ref els) => {
+                let label1 = self.new_label();
+                let mut label2 = label1.clone();
+
+                self.visit_expr(cond);
+                self.backend.jump_if_zero(&label1);
+
+                for s in then.iter() { self.visit_stmt(s); }
+
+                if let Some(ref els) = *els {
+                    label2 = self.new_label();
+                    self.backend.jump(&label2);
+
+                    self.backend.label(&label1);
+
+                    for s in els.iter() { self.visit_stmt(s); }
+                }
+
+                self.backend.label(&label2);
+            }
+
+            Stmt::While(ref cond, ref body) => {
+                let label1 = self.new_label();
+                let label2 = self.new_label();
+
+                self.backend.label(&label1);
+
+                self.visit_expr(cond);
+                self.backend.jump_if_zero(&label2);
+
+                for s in body.iter() { self.visit_stmt(s); }
+
+                self.backend.jump(&label1);
+
+                self.backend.label(&label2);
+            }
+
+            Stmt::Loop(ref body) => {
+                let label = self.new_label();
+                self.backend.label(&label);
+
+                for s in body.iter() { self.visit_stmt(s); }
+
+                self.backend.jump(&label);
+            }
+
+            Stmt::Repeat(ref body, ref cond) => {
+                let label = self.new_label();
+                self.backend.label(&label);
+
+                for s in body.iter() { self.visit_stmt(s); }
+
+                self.visit_expr(cond);
+                self.backend.jump_if_zero(&label);
+            }
+
+            Stmt::For(name, ref from, ref to, ref body) => {
+                self.backend.save_scratch();
+
+                let label1 = self.new_label();
+                let label2 = self.new_label();
+
+                let name = self.symbols.resolve(name).to_string();
+                let name = name.as_str();
+
+                self.backend.load_slot(name);
+
+                self.visit_expr(from);
+                self.backend.push();
+
+                self.visit_expr(to);
+                self.backend.binop(BinOp::Sub);
+                self.backend.jump_if_overflow(&label2);
+                self.backend.store_slot(name);
+
+                self.backend.label(&label1);
+
+                for s in body.iter() { self.visit_stmt(s); }
+
+                self.backend.dec_and_jump_if_nonzero(name, &label1);
+
+                self.backend.label(&label2);
+                self.backend.restore_scratch();
+            }
+
+            Stmt::Do(ref count, ref body) => {
+                let label = self.new_label();
+
+                self.visit_expr(count);
+                self.backend.enter_loop(&label);
+
+                for s in body.iter() { self.visit_stmt(s); }
+
+                self.backend.loop_back(&label);
+            }
+
+            Stmt::Other(name) => self.backend.other(self.symbols.resolve(name)),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match *expr {
+            Expr::Num(n) => self.backend.load_const(n),
+            Expr::Var(name) => self.backend.load_var(self.symbols.resolve(name)),
+            Expr::Binary(op, ref lhs, ref rhs) => {
+                self.visit_expr(lhs);
+                self.backend.push();
+                self.visit_expr(rhs);
+                self.backend.binop(op);
+            }
+            Expr::Compare(op, ref lhs, ref rhs) => {
+                self.visit_expr(lhs);
+                self.backend.push();
+                self.visit_expr(rhs);
+                self.backend.compare(op);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::translator_backend_vm::VmBackend;
+    use crate::translator_lexer::SymbolTable;
+    use std::cell::RefCell;
+    use std::io::{self, Write};
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct SharedBuf(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// The same AST, walked through the same `Visitor` impl, should drive
+    /// whichever `Backend` it's handed — that's the whole point of the split.
+    #[test]
+    fn drives_any_backend_through_the_same_visitor() {
+        let symbols = SymbolTable::new();
+        let buf = Rc::new(RefCell::new(Vec::new()));
+        let backend = Box::new(VmBackend::new(Box::new(SharedBuf(buf.clone()))));
+        let mut codegen = CodeGen::new(&symbols, backend);
+
+        // 2 + (3 * 4)
+        let expr = Expr::Binary(
+            BinOp::Add,
+            Box::new(Expr::Num(2)),
+            Box::new(Expr::Binary(BinOp::Mul, Box::new(Expr::Num(3)), Box::new(Expr::Num(4)))),
+        );
+        codegen.visit_expr(&expr);
+
+        let output = String::from_utf8(buf.borrow().clone()).unwrap();
+        assert_eq!(output, "PUSH 2\nPUSH 3\nPUSH 4\nMUL\nADD\n");
+    }
+}
@@ -1,258 +1,357 @@
-use std::io::stdin;
-use std::ascii::Ascii;
+use std::io::{stdin, Read};
+
+use crate::translator_ast::{BinOp, Expr, RelOp, Stmt};
+use crate::translator_errors::ParseError;
+use crate::translator_lexer::{Keyword, Lexer, Symbol, SymbolTable, Token};
+use crate::translator_span::{Span, Spanned};
 
 pub struct Translator {
-    reader: Box<Reader>,
-    look: Ascii,
-    labels: uint,
+    lexer: Lexer,
+    look: Spanned<Token>,
 }
 
 impl Translator {
+    /// Not called by the REPL, which buffers and balances input itself
+    /// before ever touching a `Translator`; predates that rewrite and has
+    /// no caller left, but reads stdin to completion in one shot if needed
+    #[allow(dead_code)]
     pub fn init() -> Translator {
+        Translator::from_reader(Box::new(stdin()))
+    }
+
+    /// Build a translator over any `Read`er — a string, a file, stdin,
+    /// whatever — reading it to completion up front so spans can point
+    /// back into the source. A reader that can't be read at all (as
+    /// opposed to one that's simply empty) yields an empty program rather
+    /// than panicking.
+    pub fn from_reader(mut reader: Box<dyn Read>) -> Translator {
+        let mut source = String::new();
+        let _ = reader.read_to_string(&mut source);
+        Translator::from_source(source)
+    }
+
+    /// Build a translator over an in-memory source string, as used by the
+    /// REPL and by `from_reader`
+    pub fn from_source(source: String) -> Translator {
         let mut t = Translator {
-            look: '\0'.to_ascii(),
-            reader: box stdin(),
-            labels: 0,
+            lexer: Lexer::new(source),
+            look: Spanned::new(Token::Eof, Span { line: 1, col: 1, offset: 0 }),
         };
-        t.read(); // this is important! reads the first char of input
+        t.read(); // this is important! reads the first token of input
         t
     }
 
-    /// Get the current lookahead character
-    pub fn look(&self) -> char {
-        self.look.to_char()
+    /// The symbol table backing every `Symbol` handed out while parsing
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.lexer.symbols
     }
 
-    /// Read the next character of input
+    /// Read the next token of input
     pub fn read(&mut self) {
-        self.look = self.reader.read_byte().ok()
-                        .expect("expected another character").to_ascii();
+        self.look = self.lexer.next();
     }
 
-    /// Check if the current character is `c`, fail otherwise
-    pub fn match_(&mut self, c: char) {
-        if self.look == c.to_ascii() {
+    /// The lookahead token's operator/punctuation character, if it has one
+    fn look_char(&self) -> Option<char> {
+        match self.look.node {
+            Token::Op(c) | Token::Punct(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Check if the current token is the operator/punctuation `c`, else
+    /// report a `ParseError` at its span
+    pub fn match_(&mut self, c: char) -> Result<(), ParseError> {
+        if self.look_char() == Some(c) {
             self.read();
+            Ok(())
         } else {
-            expected(c.to_str().as_slice());
+            Err(self.err(&format!("'{}'", c)))
         }
     }
 
-    /// Get an identifier
-    pub fn get_name(&mut self) -> Ascii {
-        let l = self.look;
-        if !l.is_alphabetic() {
-            expected("Name");
+    /// Check if the current token is the keyword `kw`, else report a
+    /// `ParseError` at its span
+    fn match_kw(&mut self, kw: Keyword) -> Result<(), ParseError> {
+        match self.look.node {
+            Token::Keyword(found) if found == kw => {}
+            _ => return Err(self.err(kw.name())),
         }
         self.read();
-        l.to_uppercase()
+        Ok(())
     }
 
-    /// Generate a unique label
-    fn new_label(&mut self) -> String {
-        let res = format!("L{}", self.labels);
-        self.labels += 1;
-        res
+    /// Get an identifier
+    pub fn get_name(&mut self) -> Result<Symbol, ParseError> {
+        match self.look.node {
+            Token::Ident(sym) => { self.read(); Ok(sym) }
+            _ => Err(self.err("a name")),
+        }
+    }
+
+    /// Get a number
+    pub fn get_num(&mut self) -> Result<i64, ParseError> {
+        match self.look.node {
+            Token::Number(n) => { self.read(); Ok(n) }
+            _ => Err(self.err("an integer")),
+        }
     }
 
-    /// Post a label to output
-    fn post_label(&self, label: &str) {
-        emit(format!("{}:", label).as_slice());
+    /// Build a `ParseError` at the current lookahead token's span
+    fn err(&self, expected: &str) -> ParseError {
+        ParseError::new(self.look.span, expected, &self.look.node.describe())
     }
 
     /// <program> ::= <block> END
-    pub fn program(&mut self) {
-        self.block();
-        if self.look.to_char() != 'e' {
-            expected("End");
+    pub fn program(&mut self) -> (Vec<Stmt>, Vec<ParseError>) {
+        let mut errors = Vec::new();
+        let stmts = self.block(&mut errors);
+        match self.look.node {
+            Token::Keyword(Keyword::End) => {}
+            _ => errors.push(self.err("End")),
         }
-        emitln("END");
+        (stmts, errors)
     }
 
-    /// <block> ::= [ <statement> ]*
-    /// <statement> ::= <if> | <while> | <loop> | <repeat> | <for> | <do> | <other>
-    fn block(&mut self) {
+    /// Skip tokens until a statement can plausibly start or the enclosing
+    /// block can plausibly end, so one bad statement doesn't prevent
+    /// reporting errors in the rest of the file
+    fn synchronize(&mut self) {
         loop {
-            match self.look.to_char() {
-                'i' => self.if_(),
-                'w' => self.while_(),
-                'p' => self.loop_(),
-                'r' => self.repeat(),
-                'f' => self.for_(),
-                'd' => self.do_(),
-                'e' | 'l' | 'u' => return,
-                _   => self.other()
+            match self.look.node {
+                Token::Eof => return,
+                Token::Keyword(Keyword::If) | Token::Keyword(Keyword::While) | Token::Keyword(Keyword::Loop) |
+                Token::Keyword(Keyword::Repeat) | Token::Keyword(Keyword::For) | Token::Keyword(Keyword::Do) |
+                Token::Keyword(Keyword::Else) | Token::Keyword(Keyword::End) | Token::Keyword(Keyword::Until) => return,
+                _ => self.read(),
             }
         }
     }
 
-    /// <if> ::= i <condition> <block> l <block> e
-    fn if_(&mut self) {
-        self.match_('i');
-
-        let label1 = self.new_label();
-        let mut label2 = label1.clone();
-
-        self.condition();
-
-        emitln(format!("JZ {}", label1).as_slice());
-
-        self.block();
-
-        if self.look.to_char() == 'l' {
-            self.match_('l');
-
-            label2 = self.new_label();
-            emitln(format!("JMP {}", label2).as_slice());
-
-            self.post_label(label1.as_slice());
-
-            self.block()
+    /// <block> ::= [ <statement> ]*
+    /// <statement> ::= <if> | <while> | <loop> | <repeat> | <for> | <do> | <other>
+    fn block(&mut self, errors: &mut Vec<ParseError>) -> Vec<Stmt> {
+        let mut stmts = Vec::new();
+        loop {
+            let result = match self.look.node {
+                Token::Keyword(Keyword::If)     => self.if_(errors),
+                Token::Keyword(Keyword::While)  => self.while_(errors),
+                Token::Keyword(Keyword::Loop)   => self.loop_(errors),
+                Token::Keyword(Keyword::Repeat) => self.repeat(errors),
+                Token::Keyword(Keyword::For)    => self.for_(errors),
+                Token::Keyword(Keyword::Do)     => self.do_(errors),
+                Token::Keyword(Keyword::Else) | Token::Keyword(Keyword::End) | Token::Keyword(Keyword::Until) => return stmts,
+                Token::Eof => return stmts,
+                _ => self.other(),
+            };
+
+            match result {
+                Ok(stmt) => stmts.push(stmt),
+                Err(e) => { errors.push(e); self.synchronize(); }
+            }
         }
-
-        self.match_('e');
-
-        self.post_label(label2.as_slice());
     }
 
-    /// <while> ::= w <condition> <block> e
-    fn while_(&mut self) {
-        self.match_('w');
-        let label1 = self.new_label();
-        let label2 = self.new_label();
+    /// <if> ::= If <condition> <block> [ Else <block> ] End
+    fn if_(&mut self, errors: &mut Vec<ParseError>) -> Result<Stmt, ParseError> {
+        self.match_kw(Keyword::If)?;
 
-        self.post_label(label1.as_slice());
+        let cond = self.condition()?;
+        let then = self.block(errors);
 
-        self.condition();
-
-        emitln(format!("JZ {}", label2).as_slice());
+        let els = if self.look.node == Token::Keyword(Keyword::Else) {
+            self.match_kw(Keyword::Else)?;
+            Some(self.block(errors))
+        } else {
+            None
+        };
 
-        self.block();
+        self.match_kw(Keyword::End)?;
 
-        self.match_('e');
+        Ok(Stmt::If(cond, then, els))
+    }
 
-        emitln(format!("JMP {}", label1).as_slice());
+    /// <while> ::= While <condition> <block> End
+    fn while_(&mut self, errors: &mut Vec<ParseError>) -> Result<Stmt, ParseError> {
+        self.match_kw(Keyword::While)?;
+        let cond = self.condition()?;
+        let body = self.block(errors);
+        self.match_kw(Keyword::End)?;
 
-        self.post_label(label2.as_slice());
+        Ok(Stmt::While(cond, body))
     }
 
-    /// <loop> ::= p <block> e
-    fn loop_(&mut self) {
-        self.match_('p');
+    /// <loop> ::= Loop <block> End
+    fn loop_(&mut self, errors: &mut Vec<ParseError>) -> Result<Stmt, ParseError> {
+        self.match_kw(Keyword::Loop)?;
+        let body = self.block(errors);
+        self.match_kw(Keyword::End)?;
 
-        let label = self.new_label();
-        self.post_label(label.as_slice());
+        Ok(Stmt::Loop(body))
+    }
 
-        self.block();
+    /// <repeat> ::= Repeat <block> Until <condition>
+    fn repeat(&mut self, errors: &mut Vec<ParseError>) -> Result<Stmt, ParseError> {
+        self.match_kw(Keyword::Repeat)?;
+        let body = self.block(errors);
+        self.match_kw(Keyword::Until)?;
+        let cond = self.condition()?;
 
-        self.match_('e');
-        emitln(format!("JMP {}", label).as_slice());
+        Ok(Stmt::Repeat(body, cond))
     }
 
-    /// <repeat> ::= r <block> u <condition>
-    fn repeat(&mut self) {
-        self.match_('r');
-
-        let label = self.new_label();
-        self.post_label(label.as_slice());
+    /// <for> ::= For <name> = <expr> <expr> <block> End
+    fn for_(&mut self, errors: &mut Vec<ParseError>) -> Result<Stmt, ParseError> {
+        self.match_kw(Keyword::For)?;
 
-        self.block();
+        let name = self.get_name()?;
+        self.match_('=')?;
 
-        self.match_('u');
+        let from = self.expression()?;
+        let to = self.expression()?;
+        let body = self.block(errors);
 
-        self.condition();
+        self.match_kw(Keyword::End)?;
 
-        emitln(format!("JZ {}", label).as_slice());
+        Ok(Stmt::For(name, from, to, body))
     }
 
-    /// <for> ::= f <name> = <expr> <expr> <block> e
-    fn for_(&mut self) {
-        emitln("PUSH EBX");
+    /// <do> = Do <expr> <block> End
+    fn do_(&mut self, errors: &mut Vec<ParseError>) -> Result<Stmt, ParseError> {
+        self.match_kw(Keyword::Do)?;
+        let count = self.expression()?;
+        let body = self.block(errors);
+        self.match_kw(Keyword::End)?;
 
-        self.match_('f');
-        let label1 = self.new_label();
-        let label2 = self.new_label();
-
-        let name = self.get_name();
-        self.match_('=');
-
-        emitln(format!("<somehow load {}>", name).as_slice());
-
-        self.expression();
-        emitln("MOV EBX, EAX");
-
-        self.expression();
+        Ok(Stmt::Do(count, body))
+    }
 
-        emitln("SUB EAX, EBX");
-        emitln(format!("JO {}", label2).as_slice());
-        emitln(format!("<somehow store EAX to {}>", name).as_slice());
+    /// <other> ::= <name>
+    fn other(&mut self) -> Result<Stmt, ParseError> {
+        Ok(Stmt::Other(self.get_name()?))
+    }
 
-        self.post_label(label1.as_slice());
+    /// <condition> ::= <expression> <relop> <expression>
+    fn condition(&mut self) -> Result<Expr, ParseError> {
+        let lhs = self.expression()?;
 
-        self.block();
+        let op = match self.look_char() {
+            Some('=') => { self.match_('=')?; RelOp::Eq }
+            Some('#') => { self.match_('#')?; RelOp::Ne }
+            Some('<') => { self.match_('<')?; RelOp::Lt }
+            Some('>') => { self.match_('>')?; RelOp::Gt }
+            _         => return Err(self.err("a relational operator")),
+        };
 
-        self.match_('e');
+        let rhs = self.expression()?;
 
-        emitln(format!("<somehow SUB {}, 1>", name).as_slice());
-        emitln(format!("JNZ {}", label1).as_slice());
+        Ok(Expr::Compare(op, Box::new(lhs), Box::new(rhs)))
+    }
 
-        self.post_label(label2.as_slice());
-        emitln("POP EBX");
+    /// <expression> ::= <term> [ (+|-) <term> ]*
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.term()?;
+        loop {
+            let op = match self.look_char() {
+                Some('+') => { self.match_('+')?; BinOp::Add }
+                Some('-') => { self.match_('-')?; BinOp::Sub }
+                _         => return Ok(expr),
+            };
+            let rhs = self.term()?;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(rhs));
+        }
     }
 
-    /// <do> = d <expr> <block> e
-    fn do_(&mut self) {
-        self.match_('d');
-        let label = self.new_label();
+    /// <term> ::= <factor> [ (*|/) <factor> ]*
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.factor()?;
+        loop {
+            let op = match self.look_char() {
+                Some('*') => { self.match_('*')?; BinOp::Mul }
+                Some('/') => { self.match_('/')?; BinOp::Div }
+                _         => return Ok(expr),
+            };
+            let rhs = self.factor()?;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(rhs));
+        }
+    }
 
-        self.expression();
-        emitln("MOV ECX, EAX");
+    /// <factor> ::= ( <expression> ) | <number> | <name>
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        match self.look.node {
+            Token::Punct('(') => {
+                self.match_('(')?;
+                let expr = self.expression()?;
+                self.match_(')')?;
+                Ok(expr)
+            }
+            Token::Number(_) => Ok(Expr::Num(self.get_num()?)),
+            Token::Ident(_)  => Ok(Expr::Var(self.get_name()?)),
+            _ => Err(self.err("an expression")),
+        }
+    }
+}
 
-        self.post_label(label.as_slice());
-        self.block();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        emitln(format!("LOOP {}", label).as_slice());
+    #[test]
+    fn expression_parsing_respects_operator_precedence() {
+        let mut t = Translator::from_source("2+3*4".to_string());
+        let expr = match t.expression() {
+            Ok(expr) => expr,
+            Err(_) => panic!("expected a valid expression"),
+        };
 
-        self.match_('e');
+        match expr {
+            Expr::Binary(BinOp::Add, ref lhs, ref rhs) => {
+                assert!(matches!(**lhs, Expr::Num(2)));
+                match **rhs {
+                    Expr::Binary(BinOp::Mul, ref l, ref r) => {
+                        assert!(matches!(**l, Expr::Num(3)));
+                        assert!(matches!(**r, Expr::Num(4)));
+                    }
+                    _ => panic!("expected a Mul on the right of the Add"),
+                }
+            }
+            _ => panic!("expected a top-level Add"),
+        }
     }
 
-    /// <other> ::= <name>
-    fn other(&mut self) {
-        emitln(self.get_name().to_str().as_slice());
+    #[test]
+    fn factor_parses_a_parenthesized_expression() {
+        let mut t = Translator::from_source("(1+2)".to_string());
+        match t.factor() {
+            Ok(expr) => assert!(matches!(expr, Expr::Binary(BinOp::Add, _, _))),
+            Err(_) => panic!("expected a valid expression"),
+        }
     }
 
-    fn condition(&mut self) {
-        emitln("<condition>");
+    #[test]
+    fn condition_parses_a_relational_operator_between_two_expressions() {
+        let mut t = Translator::from_source("1<2".to_string());
+        match t.condition() {
+            Ok(cond) => assert!(matches!(cond, Expr::Compare(RelOp::Lt, _, _))),
+            Err(_) => panic!("expected a valid condition"),
+        }
     }
 
-    fn expression(&mut self) {
-        emitln("<expression>");
+    #[test]
+    fn a_bad_condition_reports_a_parse_error_at_its_span() {
+        let mut t = Translator::from_source("1 2".to_string());
+        match t.condition() {
+            Ok(_) => panic!("expected a parse error"),
+            Err(e) => assert_eq!(e.expected, "a relational operator"),
+        }
     }
-}
-
-/// Report error
-pub fn error(s: &str) {
-    println!("Error: {}.", s);
-}
-
-/// Report error and exit
-pub fn abort(s: &str) -> ! {
-    error(s);
-    fail!();
-}
-
-/// Report what was expected and exit
-pub fn expected(s: &str) -> ! {
-    println!("Error: {} expected.", s);
-    fail!();
-}
 
-/// Output a string with tab
-pub fn emit(s: &str) {
-    print!("\t{}", s);
-}
-
-/// Output a string with tab and newlnie
-pub fn emitln(s: &str) {
-    println!("\t{}", s);
+    #[test]
+    fn from_reader_reads_any_reader_to_completion_before_parsing() {
+        let mut t = Translator::from_reader(Box::new("1<2".as_bytes()));
+        match t.condition() {
+            Ok(cond) => assert!(matches!(cond, Expr::Compare(RelOp::Lt, _, _))),
+            Err(_) => panic!("expected a valid condition"),
+        }
+    }
 }
@@ -0,0 +1,31 @@
+use crate::translator_span::Span;
+
+/// A single parse failure: what was expected, what was actually found, and
+/// where in the source it happened
+pub struct ParseError {
+    pub span: Span,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    pub fn new(span: Span, expected: &str, found: &str) -> ParseError {
+        ParseError {
+            span,
+            expected: expected.to_string(),
+            found: found.to_string(),
+        }
+    }
+
+    /// Print `<expected> expected, found <found>`, the offending source line,
+    /// and a caret under the offending column
+    pub fn report(&self, source: &str) {
+        println!("Error: {} expected, found {} (line {}, col {})",
+                 self.expected, self.found, self.span.line, self.span.col);
+
+        if let Some(line) = source.lines().nth(self.span.line - 1) {
+            println!("{}", line);
+            println!("{}^", " ".repeat(self.span.col - 1));
+        }
+    }
+}
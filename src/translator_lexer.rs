@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use crate::translator_span::{Span, Spanned};
+
+/// A cheap, copyable handle for an interned identifier spelling
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(usize);
+
+/// Interns identifier spellings so the parser can pass around a `Symbol`
+/// instead of repeatedly cloning the underlying `String`
+pub struct SymbolTable {
+    strings: Vec<String>,
+    ids: HashMap<String, Symbol>,
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        SymbolTable {
+            strings: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+
+    /// Intern `s`, returning the existing `Symbol` if already seen
+    pub fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(sym) = self.ids.get(s) {
+            return *sym;
+        }
+        let sym = Symbol(self.strings.len());
+        self.strings.push(s.to_string());
+        self.ids.insert(s.to_string(), sym);
+        sym
+    }
+
+    /// Look up the spelling behind a `Symbol`
+    pub fn resolve(&self, Symbol(id): Symbol) -> &str {
+        &self.strings[id]
+    }
+}
+
+impl Default for SymbolTable {
+    fn default() -> SymbolTable {
+        SymbolTable::new()
+    }
+}
+
+/// Recognized keywords, matched on the whole interned spelling rather than
+/// on a single leading character
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Keyword {
+    If,
+    While,
+    Loop,
+    Repeat,
+    For,
+    Do,
+    Else,
+    End,
+    Until,
+}
+
+impl Keyword {
+    fn from_spelling(s: &str) -> Option<Keyword> {
+        match s {
+            "IF"     => Some(Keyword::If),
+            "WHILE"  => Some(Keyword::While),
+            "LOOP"   => Some(Keyword::Loop),
+            "REPEAT" => Some(Keyword::Repeat),
+            "FOR"    => Some(Keyword::For),
+            "DO"     => Some(Keyword::Do),
+            "ELSE"   => Some(Keyword::Else),
+            "END"    => Some(Keyword::End),
+            "UNTIL"  => Some(Keyword::Until),
+            _        => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Keyword::If     => "If",
+            Keyword::While  => "While",
+            Keyword::Loop   => "Loop",
+            Keyword::Repeat => "Repeat",
+            Keyword::For    => "For",
+            Keyword::Do     => "Do",
+            Keyword::Else   => "Else",
+            Keyword::End    => "End",
+            Keyword::Until  => "Until",
+        }
+    }
+}
+
+/// A single lexical token
+#[derive(Clone, PartialEq)]
+pub enum Token {
+    Ident(Symbol),
+    Number(i64),
+    /// A run of digits that doesn't fit in an `i64`. Carried as a token
+    /// rather than failing right here so the usual `get_num`/`ParseError`
+    /// path reports it, instead of the lexer panicking
+    Overflow(String),
+    Keyword(Keyword),
+    Op(char),
+    Punct(char),
+    Eof,
+}
+
+impl Token {
+    /// A human-readable description, used in "expected X, found Y" diagnostics
+    pub fn describe(&self) -> String {
+        match *self {
+            Token::Ident(_)      => "an identifier".to_string(),
+            Token::Number(n)     => format!("number {}", n),
+            Token::Overflow(ref digits) => format!("out-of-range number {}", digits),
+            Token::Keyword(kw)   => format!("keyword {}", kw.name()),
+            Token::Op(c) | Token::Punct(c) => format!("'{}'", c),
+            Token::Eof           => "end of input".to_string(),
+        }
+    }
+}
+
+/// Turns the source into a `Token` stream, tracking a `Span` for every
+/// token and interning identifier spellings along the way
+pub struct Lexer {
+    src: Vec<char>,
+    idx: usize,
+    line: usize,
+    col: usize,
+    ch: Option<char>,
+    pub symbols: SymbolTable,
+}
+
+impl Lexer {
+    pub fn new(source: String) -> Lexer {
+        let src: Vec<char> = source.chars().collect();
+        let ch = src.first().copied();
+        Lexer {
+            src,
+            idx: 0,
+            line: 1,
+            col: 1,
+            ch,
+            symbols: SymbolTable::new(),
+        }
+    }
+
+    fn span(&self) -> Span {
+        Span { line: self.line, col: self.col, offset: self.idx }
+    }
+
+    /// Advance the internal lookahead character, leaving `None` at end of input
+    fn bump(&mut self) {
+        match self.ch {
+            Some('\n') => { self.line += 1; self.col = 1; }
+            Some(_)    => { self.col += 1; }
+            None       => {}
+        }
+        self.idx += 1;
+        self.ch = self.src.get(self.idx).copied();
+    }
+
+    fn skip_space(&mut self) {
+        loop {
+            match self.ch {
+                Some(c) if c.is_whitespace() => self.bump(),
+                _ => return,
+            }
+        }
+    }
+
+    /// Produce the next token, or `Token::Eof` once input is exhausted
+    pub fn next(&mut self) -> Spanned<Token> {
+        self.skip_space();
+        let span = self.span();
+
+        let tok = match self.ch {
+            None => Token::Eof,
+            Some(c) if c.is_alphabetic() => self.lex_word(),
+            Some(c) if c.is_ascii_digit() => self.lex_number(),
+            Some(c) => { self.bump(); classify_punct(c) }
+        };
+
+        Spanned::new(tok, span)
+    }
+
+    fn lex_word(&mut self) -> Token {
+        let mut spelling = String::new();
+        loop {
+            match self.ch {
+                Some(c) if c.is_alphabetic() || c.is_ascii_digit() => {
+                    spelling.push(c);
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+        let upper = spelling.to_uppercase();
+        match Keyword::from_spelling(&upper) {
+            Some(kw) => Token::Keyword(kw),
+            None => Token::Ident(self.symbols.intern(&upper)),
+        }
+    }
+
+    fn lex_number(&mut self) -> Token {
+        let mut digits = String::new();
+        loop {
+            match self.ch {
+                Some(c) if c.is_ascii_digit() => {
+                    digits.push(c);
+                    self.bump();
+                }
+                _ => break,
+            }
+        }
+        match digits.parse() {
+            Ok(n) => Token::Number(n),
+            Err(_) => Token::Overflow(digits),
+        }
+    }
+}
+
+fn classify_punct(c: char) -> Token {
+    match c {
+        '+' | '-' | '*' | '/' | '=' | '#' | '<' | '>' => Token::Op(c),
+        _ => Token::Punct(c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(source: &str) -> Vec<Token> {
+        let mut lexer = Lexer::new(source.to_string());
+        let mut toks = Vec::new();
+        loop {
+            let tok = lexer.next().node;
+            if tok == Token::Eof {
+                break;
+            }
+            toks.push(tok);
+        }
+        toks
+    }
+
+    #[test]
+    fn lexes_keywords_case_insensitively() {
+        let toks = tokens("if While loop");
+        assert!(toks == vec![
+            Token::Keyword(Keyword::If),
+            Token::Keyword(Keyword::While),
+            Token::Keyword(Keyword::Loop),
+        ]);
+    }
+
+    #[test]
+    fn interns_identifiers_to_the_same_symbol() {
+        let mut lexer = Lexer::new("foo FOO bar".to_string());
+        let a = lexer.next().node;
+        let b = lexer.next().node;
+        let c = lexer.next().node;
+        match (a, b, c) {
+            (Token::Ident(sa), Token::Ident(sb), Token::Ident(sc)) => {
+                assert!(sa == sb);
+                assert!(sa != sc);
+            }
+            _ => panic!("expected three identifiers"),
+        }
+    }
+
+    #[test]
+    fn lexes_numbers_and_punctuation() {
+        let toks = tokens("12 + (3)");
+        assert!(toks == vec![
+            Token::Number(12),
+            Token::Op('+'),
+            Token::Punct('('),
+            Token::Number(3),
+            Token::Punct(')'),
+        ]);
+    }
+
+    #[test]
+    fn overflowing_number_becomes_a_recoverable_token_instead_of_panicking() {
+        let toks = tokens("99999999999999999999");
+        assert!(toks == vec![Token::Overflow("99999999999999999999".to_string())]);
+    }
+
+    #[test]
+    fn reports_line_and_column_spans_across_newlines() {
+        let mut lexer = Lexer::new("a\n  b".to_string());
+        let first = lexer.next();
+        assert!(first.span.line == 1 && first.span.col == 1);
+        let second = lexer.next();
+        assert!(second.span.line == 2 && second.span.col == 3);
+    }
+}
@@ -0,0 +1,98 @@
+use std::io::{stdin, stdout, BufRead, BufReader, Write};
+
+use crate::translator_backend::Backend;
+use crate::translator_backend_x86::X86Backend;
+use crate::translator_codegen::CodeGen;
+use crate::translator_control::Translator;
+use crate::translator_lexer::{Keyword, Lexer, Token};
+
+/// Read a program from stdin, translate it, and loop — buffering input
+/// across lines while a block is still open, the way schala and rlox do
+/// for their interactive prompts
+pub fn run() {
+    let mut input = BufReader::new(stdin());
+    let mut buffer = String::new();
+
+    prompt(buffer.is_empty());
+
+    loop {
+        let mut line = String::new();
+        match input.read_line(&mut line) {
+            Ok(0) => return, // end of input
+            Ok(_) => {
+                buffer.push_str(&line);
+
+                if is_balanced(&buffer) {
+                    translate(&buffer);
+                    buffer = String::new();
+                }
+
+                prompt(buffer.is_empty());
+            }
+            Err(_) => return,
+        }
+    }
+}
+
+fn prompt(fresh: bool) {
+    print!("{}", if fresh { "> " } else { ". " });
+    let _ = stdout().flush();
+}
+
+/// Whether every block opened so far (`If`/`While`/`Loop`/`Repeat`/`For`/`Do`)
+/// has been closed by its matching `End`/`Until`
+fn is_balanced(source: &str) -> bool {
+    let mut lexer = Lexer::new(source.to_string());
+    let mut depth = 0i32;
+
+    loop {
+        match lexer.next().node {
+            Token::Eof => return depth <= 0,
+            Token::Keyword(Keyword::If) | Token::Keyword(Keyword::While) | Token::Keyword(Keyword::Loop) |
+            Token::Keyword(Keyword::Repeat) | Token::Keyword(Keyword::For) | Token::Keyword(Keyword::Do) => depth += 1,
+            Token::Keyword(Keyword::End) | Token::Keyword(Keyword::Until) => depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+fn translate(source: &str) {
+    let mut t = Translator::from_source(source.to_string());
+    let (stmts, errors) = t.program();
+
+    if errors.is_empty() {
+        let backend = Box::new(X86Backend::new(Box::new(stdout()))) as Box<dyn Backend>;
+        let mut codegen = CodeGen::new(t.symbols(), backend);
+        codegen.generate(&stmts);
+    } else {
+        for e in errors.iter() {
+            e.report(source);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_single_line_block_is_balanced() {
+        assert!(is_balanced("if 1<2 x end"));
+    }
+
+    #[test]
+    fn an_open_block_is_not_balanced() {
+        assert!(!is_balanced("if 1<2 x"));
+    }
+
+    #[test]
+    fn nested_blocks_must_each_close_before_the_outer_one_does() {
+        assert!(!is_balanced("if 1<2 while 1<2 x end"));
+        assert!(is_balanced("if 1<2 while 1<2 x end end"));
+    }
+
+    #[test]
+    fn repeat_until_balances_like_any_other_block() {
+        assert!(is_balanced("repeat x until 1<2"));
+    }
+}
@@ -0,0 +1,21 @@
+/// A source location: 1-based line/column for diagnostics, plus a 0-based
+/// byte offset for slicing back into the original source
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Span {
+    pub line: usize,
+    pub col: usize,
+    pub offset: usize,
+}
+
+/// Wraps a value together with the span of source it came from
+#[derive(Clone)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Spanned<T> {
+        Spanned { node, span }
+    }
+}